@@ -1,13 +1,24 @@
 #![feature(box_into_raw_non_null)]
 use vulkano::memory::DeviceMemory;
 use vulkano::memory::MappedDeviceMemory;
+use vulkano::buffer::BufferAccess;
+use vulkano::buffer::BufferUsage;
+use vulkano::buffer::CpuAccessibleBuffer;
+use vulkano::buffer::DeviceLocalBuffer;
+use vulkano::command_buffer::AutoCommandBufferBuilder;
 use vulkano::device::Device;
 use vulkano::device::DeviceExtensions;
 use vulkano::device::Features;
+use vulkano::device::Queue;
 use vulkano::instance::Instance;
 use vulkano::instance::InstanceExtensions;
 use vulkano::instance::PhysicalDevice;
+use vulkano::sync::GpuFuture;
 use std::sync::Arc;
+use std::rc::Rc;
+use core::cell::Cell;
+use core::cell::RefCell;
+use core::mem;
 use core::mem::size_of;
 use core::ptr;
 use core::ptr::NonNull;
@@ -19,31 +30,101 @@ pub struct LinkedList<T> {
     head: Option<NonNull<Node<T>>>,
     tail: Option<NonNull<Node<T>>>,
     len: usize,
+    pool: Rc<GpuAllocator<T>>,
+    // `Some` when nodes are stored device-local and must round-trip
+    // through a staging buffer; `None` for the default host-visible, pooled storage.
+    storage: Option<DeviceLocalStorage<T>>,
+    _marker: PhantomData<Box<Node<T>>>
+}
+
+/// A pool of `MappedDeviceMemory` blocks sliced into fixed-size slots for `T`.
+pub struct GpuAllocator<T> {
     device: Arc<Device>,
-    _marker: PhantomData<Box<Node<T>>>  
+    blocks: RefCell<Vec<AllocatorBlock>>,
+    slots_per_block: usize,
+    _marker: PhantomData<T>,
+}
+
+struct AllocatorBlock {
+    memory: Rc<MappedDeviceMemory>,
+    free: Vec<usize>,
+}
+
+impl<T> GpuAllocator<T> {
+    const DEFAULT_SLOTS_PER_BLOCK: usize = 1024;
+
+    pub fn new(device: Arc<Device>) -> Self {
+        Self::with_slots_per_block(device, Self::DEFAULT_SLOTS_PER_BLOCK)
+    }
+
+    pub fn with_slots_per_block(device: Arc<Device>, slots_per_block: usize) -> Self {
+        assert!(slots_per_block > 0, "slots_per_block must be greater than zero");
+        GpuAllocator {
+            device,
+            blocks: RefCell::new(Vec::new()),
+            slots_per_block,
+            _marker: PhantomData,
+        }
+    }
+
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    fn stride() -> usize {
+        size_of::<T>()
+    }
+
+    fn grow(&self) {
+        let mem_ty = self.device.physical_device().memory_types()
+                         .filter(|t| t.is_host_visible())
+                         .next().unwrap();
+        let memory = DeviceMemory::alloc_and_map(
+            self.device.clone(), mem_ty, Self::stride() * self.slots_per_block
+        ).unwrap();
+        let free = (0..self.slots_per_block).rev().collect();
+        self.blocks.borrow_mut().push(AllocatorBlock { memory: Rc::new(memory), free });
+    }
+
+    // Pops a free slot, growing by one block when every existing block is full.
+    fn alloc(&self) -> (usize, usize, Rc<MappedDeviceMemory>) {
+        loop {
+            {
+                let mut blocks = self.blocks.borrow_mut();
+                for (block, slots) in blocks.iter_mut().enumerate() {
+                    if let Some(slot) = slots.free.pop() {
+                        return (block, slot, slots.memory.clone());
+                    }
+                }
+            }
+            self.grow();
+        }
+    }
+
+    fn dealloc(&self, block: usize, slot: usize) {
+        self.blocks.borrow_mut()[block].free.push(slot);
+    }
 }
 
-struct GpuBox<T> {
-    inner: MappedDeviceMemory,
+struct PooledBox<T> {
+    pool: Rc<GpuAllocator<T>>,
+    memory: Rc<MappedDeviceMemory>,
+    block: usize,
+    slot: usize,
     _marker: PhantomData<T>,
 }
 
-impl<T> GpuBox<T> {
-    fn new(data: T, device: Arc<Device>) -> Self {
-        let mem_ty = device.physical_device().memory_types()
-                            .filter(|t| t.is_host_visible())
-                            .next().unwrap();  
-        let memory = DeviceMemory::alloc_and_map(device.clone(), mem_ty, size_of::<T>()).unwrap();
+impl<T> PooledBox<T> {
+    fn new(data: T, pool: Rc<GpuAllocator<T>>) -> Self {
+        let (block, slot, memory) = pool.alloc();
+        let offset = slot * GpuAllocator::<T>::stride();
 
         unsafe {
-            let mut content = memory.read_write::<T>(0..size_of::<T>());
+            let mut content = memory.read_write::<T>(offset..offset + size_of::<T>());
             *content = data;
         }
-        
-        GpuBox {
-            inner: memory,
-            _marker: PhantomData,
-        }
+
+        PooledBox { pool, memory, block, slot, _marker: PhantomData }
     }
 
     fn into_inner(self) -> T {
@@ -53,11 +134,153 @@ impl<T> GpuBox<T> {
     }
 
     fn as_ref(&self) -> &T {
+        let offset = self.slot * GpuAllocator::<T>::stride();
         unsafe {
-            let b = Box::new(self.inner.read_write::<T>(0..size_of::<T>()));
+            let b = Box::new(self.memory.read_write::<T>(offset..offset + size_of::<T>()));
             Box::leak(b)
         }
     }
+
+    // Unlike `as_ref`, borrows the mapping directly instead of leaking a `Box`.
+    fn as_mut(&mut self) -> &mut T {
+        let offset = self.slot * GpuAllocator::<T>::stride();
+        unsafe {
+            let mut guard = self.memory.read_write::<T>(offset..offset + size_of::<T>());
+            let ptr: *mut T = &mut *guard;
+            &mut *ptr
+        }
+    }
+}
+
+impl<T> Drop for PooledBox<T> {
+    fn drop(&mut self) {
+        self.pool.dealloc(self.block, self.slot);
+    }
+}
+
+/// Where a `LinkedList`'s node payloads live: mapped host-visible memory
+/// (the default), or fast `DeviceLocal` memory shuttled through staging.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StorageMode {
+    HostVisible,
+    DeviceLocal,
+}
+
+// The queue used to submit staging copies; each node keeps its own staging
+// buffer (see `DeviceLocalBox::staging`) rather than sharing one.
+struct DeviceLocalStorage<T> {
+    queue: Arc<Queue>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Clone for DeviceLocalStorage<T> {
+    fn clone(&self) -> Self {
+        DeviceLocalStorage { queue: self.queue.clone(), _marker: PhantomData }
+    }
+}
+
+struct DeviceLocalBox<T> {
+    buffer: Arc<DeviceLocalBuffer<T>>,
+    // This node's own staging buffer, distinct from every other node's.
+    staging: Arc<CpuAccessibleBuffer<T>>,
+    storage: DeviceLocalStorage<T>,
+    // Set by `as_mut`; tells the next `download` to flush instead of overwrite.
+    dirty: Cell<bool>,
+}
+
+impl<T> DeviceLocalBox<T> {
+    fn new(data: T, device: Arc<Device>, storage: DeviceLocalStorage<T>) -> Self {
+        let staging = CpuAccessibleBuffer::uninitialized(
+            device.clone(),
+            BufferUsage { transfer_source: true, transfer_destination: true, ..BufferUsage::none() },
+        ).unwrap();
+
+        unsafe {
+            let mut content = staging.write().unwrap();
+            *content = data;
+        }
+
+        let buffer = DeviceLocalBuffer::new(
+            device,
+            BufferUsage { transfer_source: true, transfer_destination: true, ..BufferUsage::none() },
+            Some(storage.queue.family()),
+        ).unwrap();
+
+        Self::copy(staging.clone(), buffer.clone(), &storage.queue);
+
+        DeviceLocalBox { buffer, staging, storage, dirty: Cell::new(false) }
+    }
+
+    fn copy(src: Arc<dyn BufferAccess + Send + Sync>, dst: Arc<dyn BufferAccess + Send + Sync>, queue: &Arc<Queue>) {
+        let command_buffer = AutoCommandBufferBuilder::new(queue.device().clone(), queue.family()).unwrap()
+            .copy_buffer(src, dst).unwrap()
+            .build().unwrap();
+        command_buffer.execute(queue.clone()).unwrap()
+            .then_signal_fence_and_flush().unwrap()
+            .wait(None).unwrap();
+    }
+
+    // Reconciles the staging buffer with `buffer`, flushing a pending
+    // `as_mut` write instead of overwriting it with a stale download.
+    fn download(&self) {
+        if self.dirty.replace(false) {
+            Self::copy(self.staging.clone(), self.buffer.clone(), &self.storage.queue);
+        } else {
+            Self::copy(self.buffer.clone(), self.staging.clone(), &self.storage.queue);
+        }
+    }
+
+    fn into_inner(self) -> T {
+        unsafe { ptr::read(self.as_ref()) }
+    }
+
+    fn as_ref(&self) -> &T {
+        self.download();
+        unsafe {
+            let guard = self.staging.read().unwrap();
+            let ptr: *const T = &*guard;
+            &*ptr
+        }
+    }
+
+    // Marks `dirty` so the next `download` flushes this write out to `buffer`.
+    fn as_mut(&mut self) -> &mut T {
+        self.download();
+        self.dirty.set(true);
+        unsafe {
+            let mut guard = self.staging.write().unwrap();
+            let ptr: *mut T = &mut *guard;
+            &mut *ptr
+        }
+    }
+}
+
+enum GpuBox<T> {
+    Pooled(PooledBox<T>),
+    DeviceLocal(DeviceLocalBox<T>),
+}
+
+impl<T> GpuBox<T> {
+    fn into_inner(self) -> T {
+        match self {
+            GpuBox::Pooled(b) => b.into_inner(),
+            GpuBox::DeviceLocal(b) => b.into_inner(),
+        }
+    }
+
+    fn as_ref(&self) -> &T {
+        match self {
+            GpuBox::Pooled(b) => b.as_ref(),
+            GpuBox::DeviceLocal(b) => b.as_ref(),
+        }
+    }
+
+    fn as_mut(&mut self) -> &mut T {
+        match self {
+            GpuBox::Pooled(b) => b.as_mut(),
+            GpuBox::DeviceLocal(b) => b.as_mut(),
+        }
+    }
 }
 
 struct Node<T> {
@@ -68,6 +291,11 @@ struct Node<T> {
 
 impl<T> LinkedList<T> {
     pub fn new() -> Self {
+        let (device, _queue) = Self::default_device_and_queue();
+        Self::with_device(device)
+    }
+
+    fn default_device_and_queue() -> (Arc<Device>, Arc<Queue>) {
         let instance = Instance::new(None, &InstanceExtensions::none(), None)
             .expect("failed to create instance");
         let physical = PhysicalDevice::enumerate(&instance).next().expect("no device available");
@@ -75,26 +303,57 @@ impl<T> LinkedList<T> {
             .find(|&q| q.supports_graphics())
             .expect("couldn't find a graphical queue family");
 
-        let (device, mut _queues) = {
+        let (device, mut queues) = {
             Device::new(physical, &Features::none(), &DeviceExtensions::none(),
                         [(queue_family, 0.5)].iter().cloned()).expect("failed to create device")
         };
-        Self::with_device(device)
+        let queue = queues.next().expect("device was created without a queue");
+        (device, queue)
     }
 
     fn with_device(device: Arc<Device>) -> Self {
+        Self::with_allocator(GpuAllocator::new(device))
+    }
+
+    /// Builds a list that draws its nodes from `pool` instead of a default one.
+    pub fn with_allocator(pool: GpuAllocator<T>) -> Self {
+        Self::with_pool(Rc::new(pool), None)
+    }
+
+    /// Builds a list whose node payloads are stored according to `mode`.
+    pub fn with_storage(device: Arc<Device>, queue: Arc<Queue>, mode: StorageMode) -> Self {
+        match mode {
+            StorageMode::HostVisible => Self::with_device(device),
+            StorageMode::DeviceLocal => {
+                let storage = DeviceLocalStorage { queue, _marker: PhantomData };
+                Self::with_pool(Rc::new(GpuAllocator::new(device)), Some(storage))
+            }
+        }
+    }
+
+    fn with_pool(pool: Rc<GpuAllocator<T>>, storage: Option<DeviceLocalStorage<T>>) -> Self {
         LinkedList {
             head: None,
             tail: None,
             len: 0,
-            device,
+            pool,
+            storage,
             _marker: PhantomData
         }
     }
 
+    fn alloc_box(&self, data: T) -> GpuBox<T> {
+        match &self.storage {
+            None => GpuBox::Pooled(PooledBox::new(data, self.pool.clone())),
+            Some(storage) => GpuBox::DeviceLocal(
+                DeviceLocalBox::new(data, self.pool.device().clone(), storage.clone())
+            ),
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.len
-    } 
+    }
 
     pub fn iter(&self) -> Iter<T> {
         Iter {
@@ -104,6 +363,112 @@ impl<T> LinkedList<T> {
             _marker: PhantomData,
         }
     }
+
+    pub fn iter_mut(&mut self) -> IterMut<T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn cursor_front_mut(&mut self) -> CursorMut<T> {
+        CursorMut {
+            current: self.head,
+            index: 0,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<T> {
+        let index = self.len.checked_sub(1).unwrap_or(0);
+        CursorMut {
+            current: self.tail,
+            index,
+            list: self,
+        }
+    }
+
+    /// Moves all of `other`'s nodes onto the end of `self` in O(1), leaving
+    /// `other` empty. Both lists must have been created on the same
+    /// `Arc<Device>`.
+    pub fn append(&mut self, other: &mut LinkedList<T>) {
+        match self.tail {
+            None => mem::swap(self, other),
+            Some(mut tail) => {
+                if let Some(mut other_head) = other.head.take() {
+                    unsafe {
+                        tail.as_mut().next = Some(other_head);
+                        other_head.as_mut().prev = Some(tail);
+                    }
+                    self.tail = other.tail.take();
+                    self.len += mem::replace(&mut other.len, 0);
+                }
+            }
+        }
+    }
+
+    /// Splits the list into two at the given index, returning a new list
+    /// holding everything from `at` onward and leaving `self` with
+    /// everything before it. The returned list shares `self`'s allocator
+    /// pool, so it is guaranteed to live on the same `Arc<Device>`.
+    pub fn split_off(&mut self, at: usize) -> LinkedList<T> {
+        let len = self.len;
+        assert!(at <= len, "Cannot split off at a nonexistent index");
+
+        if at == 0 {
+            return mem::replace(self, LinkedList::with_pool(self.pool.clone(), self.storage.clone()));
+        } else if at == len {
+            return LinkedList::with_pool(self.pool.clone(), self.storage.clone());
+        }
+
+        let split_node = if at - 1 <= len - 1 - at {
+            let mut node = self.head;
+            for _ in 0..at - 1 {
+                node = unsafe { node.unwrap().as_ref().next };
+            }
+            node
+        } else {
+            let mut node = self.tail;
+            for _ in 0..len - at {
+                node = unsafe { node.unwrap().as_ref().prev };
+            }
+            node
+        }.unwrap();
+
+        unsafe {
+            let mut second_head = split_node.as_ref().next.unwrap();
+            second_head.as_mut().prev = None;
+            split_node.as_mut().next = None;
+
+            let mut second = LinkedList::with_pool(self.pool.clone(), self.storage.clone());
+            second.head = Some(second_head);
+            second.tail = self.tail;
+            second.len = len - at;
+
+            self.tail = Some(split_node);
+            self.len = at;
+
+            second
+        }
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = LinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
 }
 
 macro_rules! push_pop_impl {
@@ -111,10 +476,11 @@ macro_rules! push_pop_impl {
     $new_node_from: ident, $old_node_from: ident, $new_node_dir: ident, $old_node_dir: ident) => {
         
     pub fn $push_fn(&mut self, data: $inner_type) {
+        let data = self.alloc_box(data);
         let mut new_node = Box::new(Node {
             prev: None,
             next: None,
-            data: GpuBox::new(data, self.device.clone()),
+            data,
         });
         new_node.$old_node_dir = self.$old_node_from;
         let new_node = Some(Box::into_raw_non_null(new_node));
@@ -226,10 +592,254 @@ impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
 
 impl<'a, T> FusedIterator for Iter<'a, T> {}
 
+pub struct IterMut<'a, T: 'a> {
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    len: usize,
+    _marker: PhantomData<&'a mut Node<T>>,
+}
+
+impl<'a, T: 'a + fmt::Debug> fmt::Debug for IterMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("IterMut")
+         .field(&self.len)
+         .finish()
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.head.map(|node| unsafe {
+                let node = &mut *node.as_ptr();
+                self.len -= 1;
+                self.head = node.next;
+                node.data.as_mut()
+            })
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.tail.map(|node| unsafe {
+                let node = &mut *node.as_ptr(); // unbounded lifetime
+                self.len -= 1;
+                self.tail = node.prev;
+                node.data.as_mut()
+            })
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T> FusedIterator for IterMut<'a, T> {}
+
+/// A cursor over a `LinkedList` that can insert and remove elements at its
+/// current position in O(1). `current == None` is the "ghost" position past
+/// either end, cycling back to the opposite end on the next move.
+pub struct CursorMut<'a, T: 'a> {
+    current: Option<NonNull<Node<T>>>,
+    index: usize,
+    list: &'a mut LinkedList<T>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    /// Returns the cursor's index, or `None` while it rests on the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current.take() {
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+            Some(current) => unsafe {
+                self.current = current.as_ref().next;
+                if self.current.is_some() {
+                    self.index += 1;
+                } else {
+                    self.index = 0;
+                }
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current.take() {
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.len.checked_sub(1).unwrap_or(0);
+            }
+            Some(current) => unsafe {
+                self.current = current.as_ref().prev;
+                if self.current.is_some() {
+                    self.index -= 1;
+                } else {
+                    self.index = self.list.len;
+                }
+            }
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.current.map(|mut node| node.as_mut().data.as_mut()) }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            let next = match self.current {
+                None => self.list.head,
+                Some(node) => node.as_ref().next,
+            };
+            next.map(|mut node| node.as_mut().data.as_mut())
+        }
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe {
+            let prev = match self.current {
+                None => self.list.tail,
+                Some(node) => node.as_ref().prev,
+            };
+            prev.map(|mut node| node.as_mut().data.as_mut())
+        }
+    }
+
+    /// Inserts `item` immediately after the current position. Inserting
+    /// from the ghost position puts `item` at the front of the list.
+    pub fn insert_after(&mut self, item: T) {
+        unsafe {
+            let new_node = Box::new(Node {
+                prev: self.current,
+                next: None,
+                data: self.list.alloc_box(item),
+            });
+            let mut new_node = Box::into_raw_non_null(new_node);
+            match self.current {
+                None => {
+                    new_node.as_mut().next = self.list.head;
+                    match self.list.head {
+                        Some(mut head) => head.as_mut().prev = Some(new_node),
+                        None => self.list.tail = Some(new_node),
+                    }
+                    self.list.head = Some(new_node);
+                }
+                Some(mut current) => {
+                    let next = current.as_ref().next;
+                    new_node.as_mut().next = next;
+                    current.as_mut().next = Some(new_node);
+                    match next {
+                        Some(mut next) => next.as_mut().prev = Some(new_node),
+                        None => self.list.tail = Some(new_node),
+                    }
+                }
+            }
+            self.list.len += 1;
+        }
+    }
+
+    /// Inserts `item` immediately before the current position. Inserting
+    /// from the ghost position puts `item` at the back of the list.
+    pub fn insert_before(&mut self, item: T) {
+        unsafe {
+            let new_node = Box::new(Node {
+                prev: None,
+                next: self.current,
+                data: self.list.alloc_box(item),
+            });
+            let mut new_node = Box::into_raw_non_null(new_node);
+            match self.current {
+                None => {
+                    new_node.as_mut().prev = self.list.tail;
+                    match self.list.tail {
+                        Some(mut tail) => tail.as_mut().next = Some(new_node),
+                        None => self.list.head = Some(new_node),
+                    }
+                    self.list.tail = Some(new_node);
+                }
+                Some(mut current) => {
+                    let prev = current.as_ref().prev;
+                    new_node.as_mut().prev = prev;
+                    current.as_mut().prev = Some(new_node);
+                    match prev {
+                        Some(mut prev) => prev.as_mut().next = Some(new_node),
+                        None => self.list.head = Some(new_node),
+                    }
+                    self.index += 1;
+                }
+            }
+        }
+        self.list.len += 1;
+    }
+
+    /// Removes the current node and advances the cursor to the node that
+    /// followed it, returning the removed value.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let current = self.current.take()?;
+        unsafe {
+            let node = Box::from_raw(current.as_ptr());
+            match node.prev {
+                Some(mut prev) => prev.as_mut().next = node.next,
+                None => self.list.head = node.next,
+            }
+            match node.next {
+                Some(mut next) => next.as_mut().prev = node.prev,
+                None => self.list.tail = node.prev,
+            }
+            self.list.len -= 1;
+            self.current = node.next;
+            if self.current.is_none() {
+                self.index = self.list.len;
+            }
+            Some(node.data.into_inner())
+        }
+    }
+}
+
 #[cfg(test)]
 mod linked_list_tests {
 
     use super::*;
+
+    // Forces `GpuAllocator::grow` to run more than once so the free-list
+    // bookkeeping across multiple blocks is actually exercised, not just
+    // the single-block case every other test happens to stay within.
+    #[test]
+    fn test_allocator_grows_past_one_block() {
+        let (device, _queue) = LinkedList::<i32>::default_device_and_queue();
+        let pool = GpuAllocator::with_slots_per_block(device, 2);
+        let mut list = LinkedList::with_allocator(pool);
+
+        for value in 1..=5 {
+            list.push_back(value);
+        }
+        assert_eq!(5, list.len());
+        assert_eq!(vec![1, 2, 3, 4, 5], list.iter().cloned().collect::<Vec<_>>());
+
+        for value in (1..=5).rev() {
+            assert_eq!(Some(value), list.pop_back());
+        }
+        assert_eq!(None, list.pop_back());
+    }
+
     #[test]
     fn test_push_pop() {
         let mut list = LinkedList::new();
@@ -264,4 +874,225 @@ mod linked_list_tests {
         list.push_back(Data(3));
         // Now list is out of scope
     }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut list = LinkedList::new();
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+        for value in list.iter_mut() {
+            *value *= 10;
+        }
+        assert_eq!(vec![10, 20, 30], list.iter().cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_cursor_mid_insert_remove() {
+        let mut list = LinkedList::new();
+        list.push_back(1);
+        list.push_back(3);
+
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(Some(&mut 1), cursor.current());
+        cursor.insert_after(2);
+        cursor.move_next();
+        assert_eq!(Some(&mut 2), cursor.current());
+        assert_eq!(Some(2), cursor.remove_current());
+        assert_eq!(Some(&mut 3), cursor.current());
+
+        assert_eq!(vec![1, 3], list.iter().cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_cursor_insert_before() {
+        let mut list = LinkedList::new();
+        list.push_back(1);
+        list.push_back(3);
+
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(Some(&mut 3), cursor.current());
+        cursor.insert_before(2);
+        assert_eq!(Some(&mut 3), cursor.current());
+
+        assert_eq!(vec![1, 2, 3], list.iter().cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_cursor_back_mut() {
+        let mut list = LinkedList::new();
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        let mut cursor = list.cursor_back_mut();
+        assert_eq!(Some(2), cursor.index());
+        assert_eq!(Some(&mut 3), cursor.current());
+    }
+
+    #[test]
+    fn test_cursor_move_prev() {
+        let mut list = LinkedList::new();
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_prev();
+        assert_eq!(Some(&mut 2), cursor.current());
+        cursor.move_prev();
+        assert_eq!(Some(&mut 1), cursor.current());
+    }
+
+    #[test]
+    fn test_cursor_peek_next_prev() {
+        let mut list = LinkedList::new();
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(None, cursor.peek_prev());
+        assert_eq!(Some(&mut 2), cursor.peek_next());
+        cursor.move_next();
+        assert_eq!(Some(&mut 1), cursor.peek_prev());
+        assert_eq!(Some(&mut 3), cursor.peek_next());
+    }
+
+    #[test]
+    fn test_cursor_ghost_wraparound() {
+        let mut list = LinkedList::new();
+        list.push_back(1);
+        list.push_back(2);
+
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(Some(0), cursor.index());
+
+        // Moving past the tail lands on the ghost position, identified by
+        // a `None` index; moving again from there wraps to the front.
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(None, cursor.index());
+        cursor.move_next();
+        assert_eq!(Some(0), cursor.index());
+        assert_eq!(Some(&mut 1), cursor.current());
+
+        // The same wraparound holds moving backwards past the head.
+        cursor.move_prev();
+        assert_eq!(None, cursor.index());
+        cursor.move_prev();
+        assert_eq!(Some(1), cursor.index());
+        assert_eq!(Some(&mut 2), cursor.current());
+    }
+
+    #[test]
+    fn test_from_iter_extend() {
+        let mut list: LinkedList<i32> = (1..=3).collect();
+        list.extend(vec![4, 5]);
+        assert_eq!(vec![1, 2, 3, 4, 5], list.iter().cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_append_split_off() {
+        let mut a: LinkedList<i32> = (1..=3).collect();
+        let mut b = a.split_off(1);
+        assert_eq!(vec![1], a.iter().cloned().collect::<Vec<_>>());
+        assert_eq!(vec![2, 3], b.iter().cloned().collect::<Vec<_>>());
+
+        a.append(&mut b);
+        assert_eq!(vec![1, 2, 3], a.iter().cloned().collect::<Vec<_>>());
+        assert_eq!(0, b.len());
+    }
+
+    #[test]
+    fn test_split_off_backward_traversal() {
+        // `at - 1 > len - 1 - at` here, so the split point is found by
+        // walking from the tail instead of the head.
+        let mut a: LinkedList<i32> = (1..=5).collect();
+        let b = a.split_off(4);
+        assert_eq!(vec![1, 2, 3, 4], a.iter().cloned().collect::<Vec<_>>());
+        assert_eq!(vec![5], b.iter().cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_split_off_at_zero_and_at_len() {
+        let mut a: LinkedList<i32> = (1..=3).collect();
+        let b = a.split_off(0);
+        assert_eq!(Vec::<i32>::new(), a.iter().cloned().collect::<Vec<_>>());
+        assert_eq!(vec![1, 2, 3], b.iter().cloned().collect::<Vec<_>>());
+
+        let mut a: LinkedList<i32> = (1..=3).collect();
+        let b = a.split_off(3);
+        assert_eq!(vec![1, 2, 3], a.iter().cloned().collect::<Vec<_>>());
+        assert_eq!(Vec::<i32>::new(), b.iter().cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_append_onto_empty_list() {
+        let mut a: LinkedList<i32> = LinkedList::new();
+        let mut b: LinkedList<i32> = (1..=3).collect();
+        a.append(&mut b);
+        assert_eq!(vec![1, 2, 3], a.iter().cloned().collect::<Vec<_>>());
+        assert_eq!(0, b.len());
+    }
+
+    #[test]
+    fn test_append_empty_other() {
+        let mut a: LinkedList<i32> = (1..=3).collect();
+        let mut b: LinkedList<i32> = LinkedList::new();
+        a.append(&mut b);
+        assert_eq!(vec![1, 2, 3], a.iter().cloned().collect::<Vec<_>>());
+        assert_eq!(0, b.len());
+    }
+
+    #[test]
+    fn test_device_local_storage() {
+        let (device, queue) = LinkedList::<i32>::default_device_and_queue();
+        let mut list = LinkedList::with_storage(device, queue, StorageMode::DeviceLocal);
+        list.push_back(1);
+        list.push_back(2);
+        assert_eq!(Some(1), list.pop_front());
+        assert_eq!(Some(2), list.pop_front());
+        assert_eq!(None, list.pop_front());
+    }
+
+    // A write after a read (`pop_front`'s `into_inner` reads a staging buffer,
+    // then `push_back` writes the new node's own staging buffer) must not
+    // panic on a still-held read lock and must not lose a mutation made
+    // through `iter_mut`.
+    #[test]
+    fn test_device_local_storage_write_after_read() {
+        let (device, queue) = LinkedList::<i32>::default_device_and_queue();
+        let mut list = LinkedList::with_storage(device, queue, StorageMode::DeviceLocal);
+        list.push_back(1);
+        assert_eq!(Some(1), list.pop_front());
+        list.push_back(2);
+
+        for value in list.iter_mut() {
+            *value += 10;
+        }
+
+        assert_eq!(Some(12), list.pop_front());
+        assert_eq!(None, list.pop_front());
+    }
+
+    // Each node must own its own staging buffer: iterating a multi-element
+    // `DeviceLocal` list used to download every node into one shared
+    // staging buffer, so `next()` silently overwrote the value behind
+    // references already handed out for earlier nodes.
+    #[test]
+    fn test_device_local_storage_iter_multi_element() {
+        let (device, queue) = LinkedList::<i32>::default_device_and_queue();
+        let mut list = LinkedList::with_storage(device, queue, StorageMode::DeviceLocal);
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        assert_eq!(vec![1, 2, 3], list.iter().cloned().collect::<Vec<_>>());
+
+        for value in list.iter_mut() {
+            *value *= 10;
+        }
+        assert_eq!(vec![10, 20, 30], list.iter().cloned().collect::<Vec<_>>());
+    }
 }